@@ -0,0 +1,4 @@
+//! Functionality extensions for penrose
+pub mod scratchpad;
+
+pub use scratchpad::Scratchpad;
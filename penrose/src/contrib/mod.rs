@@ -0,0 +1,6 @@
+//! Extensions and additional functionality for penrose
+
+pub mod actions;
+pub mod extensions;
+pub mod hooks;
+pub mod layouts;
@@ -0,0 +1,253 @@
+//! Simple data types and enums
+use crate::{
+    hooks,
+    layout::{side_stack, Layout, LayoutConf},
+};
+use std::collections::HashMap;
+
+/// Output of a Layout function: the new position a window should take
+pub type ResizeAction = (WinId, Option<Region>);
+
+/// An X window ID
+pub type WinId = u32;
+
+/// An x,y coordinate pair
+#[derive(Debug, Copy, Clone)]
+pub struct Point {
+    /// An absolute x coordinate relative to the root window
+    pub x: u32,
+    /// An absolute y coordinate relative to the root window
+    pub y: u32,
+}
+
+impl Point {
+    /// Create a new Point.
+    pub fn new(x: u32, y: u32) -> Point {
+        Point { x, y }
+    }
+}
+
+/// The main user facing configuration details
+pub struct Config<'a> {
+    /// Default workspace names to use when initialising the WindowManager. Must have at least one element.
+    pub workspaces: Vec<&'a str>,
+    /// WM_CLASS values that should always be treated as floating.
+    pub floating_classes: &'static [&'static str],
+    /// Default Layouts to be given to every workspace.
+    pub layouts: Vec<Layout>,
+    /// Per-workspace overrides for `layouts`, keyed by workspace name. Workspaces with no entry
+    /// here fall back to the default `layouts` set.
+    pub per_workspace_layouts: HashMap<String, Vec<Layout>>,
+    /// Focused boder color
+    pub focused_border: u32,
+    /// Unfocused boder color
+    pub unfocused_border: u32,
+    /// Border color painted on clients that are currently marked as urgent
+    pub urgent_border: u32,
+    /// '_NET_WM_WINDOW_OPACITY' applied to the focused client (requires a running compositor)
+    pub active_opacity: f32,
+    /// '_NET_WM_WINDOW_OPACITY' applied to unfocused clients (requires a running compositor)
+    pub inactive_opacity: f32,
+    /// Per WM_CLASS overrides for the opacity that would otherwise be set by `active_opacity` /
+    /// `inactive_opacity`, e.g. to keep a launcher fully opaque at all times.
+    pub opacity_overrides: HashMap<String, f32>,
+    /// The width of window borders in pixels
+    pub border_px: u32,
+    /// The size of gaps between windows in pixels.
+    pub gap_px: u32,
+    /// The percentage change in main_ratio to be applied when increasing / decreasing.
+    pub main_ratio_step: f32,
+    /// Whether or not space should be reserved for a status bar
+    pub show_bar: bool,
+    /// True if the status bar should be at the top of the screen, false if it should be at the bottom
+    pub top_bar: bool,
+    /// Height of space reserved for status bars in pixels
+    pub bar_height: u32,
+    /// User supplied Hooks for modifying WindowManager behaviour
+    pub hooks: Vec<Box<dyn hooks::Hook>>,
+    /// Where (if at all) the X pointer should be warped to within the focused client when focus
+    /// changes. Set to `None` to leave pointer movement entirely up to the user.
+    pub pointer_warp: Option<Snap>,
+    /// Used by `WindowManager::rename_workspace`, `select_workspace` and `layout_prompt` to
+    /// interactively request a short piece of text from the user. Requires the `draw` feature:
+    /// see `draw::Prompt`. Leave as `None` to have those actions log a warning and do nothing.
+    #[cfg(feature = "draw")]
+    pub prompt: Option<Box<dyn crate::draw::PromptRunner>>,
+}
+
+impl<'a> Default for Config<'a> {
+    /// Initialise a default Config, giving sensible (but minimal) values for all fields.
+    fn default() -> Config<'a> {
+        Config {
+            workspaces: vec!["1", "2", "3", "4", "5", "6", "7", "8", "9"],
+            floating_classes: &["dmenu", "dunst"],
+            layouts: vec![
+                Layout::new("[side]", LayoutConf::default(), side_stack, 1, 0.6),
+                Layout::floating("[----]"),
+            ],
+            per_workspace_layouts: HashMap::new(),
+            focused_border: 0xcc241d,   // #cc241d
+            unfocused_border: 0x3c3836, // #3c3836
+            urgent_border: 0xcc241d,    // #cc241d
+            active_opacity: 1.0,
+            inactive_opacity: 1.0,
+            opacity_overrides: HashMap::new(),
+            border_px: 2,
+            gap_px: 5,
+            main_ratio_step: 0.05,
+            show_bar: true,
+            top_bar: true,
+            bar_height: 18,
+            hooks: vec![],
+            pointer_warp: Some(Snap::Center),
+            #[cfg(feature = "draw")]
+            prompt: None,
+        }
+    }
+}
+
+impl<'a> Config<'a> {
+    /// Create a range from 1 -> n_workspaces for use in keybindings
+    pub fn ws_range(&self) -> std::ops::Range<usize> {
+        1..(self.workspaces.len() + 1)
+    }
+}
+
+/* Argument enums */
+
+/// Increment / decrement a value
+#[derive(Debug, Copy, Clone)]
+pub enum Change {
+    /// increase the value
+    More,
+    /// decrease the value, possibly clamping
+    Less,
+}
+
+/// Where to warp the X pointer to within a client window when it gains focus.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Snap {
+    /// Warp the pointer to the center of the window
+    Center,
+    /// Warp the pointer to the bottom right corner of the window
+    Corner,
+    /// Only warp the pointer if it is currently outside of the window, moving it to the nearest
+    /// point inside the window rect
+    NearestInside,
+}
+
+/// X window border kind
+#[derive(Debug)]
+pub enum Border {
+    /// window is urgent
+    Urgent,
+    /// window currently has focus
+    Focused,
+    /// window does not have focus
+    Unfocused,
+}
+
+/// An X window / screen position: top left corner + extent
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Region {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+impl Region {
+    /// Create a new Region.
+    pub fn new(x: u32, y: u32, w: u32, h: u32) -> Region {
+        Region { x, y, w, h }
+    }
+
+    /// Destructure this Region into its component values (x, y, w, h).
+    pub fn values(&self) -> (u32, u32, u32, u32) {
+        (self.x, self.y, self.w, self.h)
+    }
+
+    /// Divides this region into two columns where the first has the given width.
+    ///
+    /// Panics if new_width is not within the region.
+    pub fn split_at_width(&self, new_width: u32) -> (Region, Region) {
+        assert!(new_width < self.w, "Split out of range.");
+        (
+            Region {
+                w: new_width,
+                ..*self
+            },
+            Region {
+                x: self.x + new_width,
+                w: self.w - new_width,
+                ..*self
+            },
+        )
+    }
+
+    /// Divides this region into two rows where the first has the given height.
+    ///
+    /// Panics if new_height is not within the region.
+    pub fn split_at_height(&self, new_height: u32) -> (Region, Region) {
+        assert!(new_height < self.h, "Split out of range.");
+        (
+            Region {
+                h: new_height,
+                ..*self
+            },
+            Region {
+                y: self.y + new_height,
+                h: self.h - new_height,
+                ..*self
+            },
+        )
+    }
+}
+
+/// Space reserved around the edges of a screen by dock / panel windows that have set the
+/// '_NET_WM_STRUT' or '_NET_WM_STRUT_PARTIAL' property.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Struts {
+    /// Reserved space on the left of the screen
+    pub left: u32,
+    /// Reserved space on the right of the screen
+    pub right: u32,
+    /// Reserved space at the top of the screen
+    pub top: u32,
+    /// Reserved space at the bottom of the screen
+    pub bottom: u32,
+}
+
+impl Struts {
+    /// Combine with another set of Struts, keeping the largest reservation for each edge.
+    pub fn max(&self, other: Struts) -> Struts {
+        Struts {
+            left: self.left.max(other.left),
+            right: self.right.max(other.right),
+            top: self.top.max(other.top),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn struts_max_keeps_largest_reservation_per_edge() {
+        let a = Struts { left: 10, right: 0, top: 5, bottom: 0 };
+        let b = Struts { left: 0, right: 20, top: 0, bottom: 15 };
+
+        assert_eq!(
+            a.max(b),
+            Struts { left: 10, right: 20, top: 5, bottom: 15 }
+        );
+    }
+
+    #[test]
+    fn struts_max_with_default_is_a_no_op() {
+        let a = Struts { left: 3, right: 4, top: 5, bottom: 6 };
+        assert_eq!(a.max(Struts::default()), a);
+    }
+}
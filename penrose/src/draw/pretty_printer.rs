@@ -0,0 +1,329 @@
+//! A Hook for rendering workspace / title / layout state as a single templated line for
+//! consumption by external status bars (e.g. xmobar) instead of drawing our own bar window.
+use crate::{client::Client, data_types::WinId, hooks::Hook, Result, Selector, WindowManager};
+
+use anyhow::anyhow;
+use nix::{
+    errno::Errno,
+    fcntl::{open, OFlag},
+    sys::stat::Mode,
+    Error as NixError,
+};
+use std::fs::File;
+use std::io::Write;
+use std::os::unix::io::FromRawFd;
+
+#[derive(Debug, Clone)]
+struct WsState {
+    name: String,
+    urgent: bool,
+}
+
+// Where a rendered status line is written to.
+#[derive(Debug, Clone)]
+enum Sink {
+    Pipe(String),
+    Stdout,
+}
+
+/// Construct a new [PrettyPrinter] with formatting for each of its fields and a sink to write
+/// the rendered status line to. See `PrettyPrinter::build` for details of the required calls.
+pub struct PrettyPrinterBuilder {
+    workspace_format: Box<dyn Fn(&str, bool, bool) -> String>,
+    title_format: Box<dyn Fn(&str) -> String>,
+    layout_format: Box<dyn Fn(&str) -> String>,
+    separator: String,
+    sink: Option<Sink>,
+}
+
+impl Default for PrettyPrinterBuilder {
+    fn default() -> Self {
+        Self {
+            workspace_format: Box::new(|ws, _, _| ws.to_string()),
+            title_format: Box::new(|title| title.to_string()),
+            layout_format: Box::new(|symbol| symbol.to_string()),
+            separator: " ".to_string(),
+            sink: None,
+        }
+    }
+}
+
+impl PrettyPrinterBuilder {
+    /// Set the formatting applied to each workspace name before being joined with `separator`.
+    /// Called with the workspace name, whether it is focused on the active screen, and whether
+    /// it is currently demanding attention (see `Hook::urgent_hook`).
+    pub fn workspace_format(mut self, f: impl Fn(&str, bool, bool) -> String + 'static) -> Self {
+        self.workspace_format = Box::new(f);
+        self
+    }
+
+    /// Set the formatting applied to the WM_NAME of the currently focused client.
+    pub fn title_format(mut self, f: impl Fn(&str) -> String + 'static) -> Self {
+        self.title_format = Box::new(f);
+        self
+    }
+
+    /// Set the formatting applied to the symbol of the active workspace's current layout.
+    pub fn layout_format(mut self, f: impl Fn(&str) -> String + 'static) -> Self {
+        self.layout_format = Box::new(f);
+        self
+    }
+
+    /// Set the separator used to join workspaces, the layout symbol and the active client title.
+    /// Defaults to a single space.
+    pub fn separator(mut self, sep: impl Into<String>) -> Self {
+        self.separator = sep.into();
+        self
+    }
+
+    /// Write the rendered status line to the named pipe at `path` every time it changes.
+    pub fn sink_to_pipe(mut self, path: impl Into<String>) -> Self {
+        self.sink = Some(Sink::Pipe(path.into()));
+        self
+    }
+
+    /// Write the rendered status line to stdout every time it changes, for users who don't want
+    /// to manage a FIFO.
+    pub fn sink_to_stdout(mut self) -> Self {
+        self.sink = Some(Sink::Stdout);
+        self
+    }
+
+    /// Finish building this PrettyPrinter. Fails if no sink has been set via `sink_to_pipe` or
+    /// `sink_to_stdout`.
+    pub fn build(self) -> Result<PrettyPrinter> {
+        let sink = self.sink.ok_or_else(|| {
+            anyhow!("PrettyPrinter requires a sink: call sink_to_pipe or sink_to_stdout")
+        })?;
+
+        Ok(PrettyPrinter {
+            workspaces: Vec::new(),
+            focused_ws: Vec::new(),
+            title: String::new(),
+            layout_symbol: String::new(),
+            workspace_format: self.workspace_format,
+            title_format: self.title_format,
+            layout_format: self.layout_format,
+            separator: self.separator,
+            sink,
+            file: None,
+        })
+    }
+}
+
+/**
+ * Render workspace / title / layout state as a single templated line and write it out to a named
+ * pipe on every change, for consumption by an external status bar such as xmobar rather than
+ * drawing our own bar window (see `draw::bar::dwm_bar` if you want penrose to draw its own bar).
+ */
+pub struct PrettyPrinter {
+    workspaces: Vec<WsState>,
+    focused_ws: Vec<usize>, // focused workspace index per screen
+    title: String,
+    layout_symbol: String,
+    workspace_format: Box<dyn Fn(&str, bool, bool) -> String>,
+    title_format: Box<dyn Fn(&str) -> String>,
+    layout_format: Box<dyn Fn(&str) -> String>,
+    separator: String,
+    sink: Sink,
+    // Held open for the lifetime of the PrettyPrinter so that re-opening the pipe sink on every
+    // flush doesn't race a reader attaching/detaching. Re-created on demand (see `ensure_sink`).
+    // Unused when `sink` is `Sink::Stdout`.
+    file: Option<File>,
+}
+
+impl PrettyPrinter {
+    /// Start building a new PrettyPrinter hook.
+    pub fn build() -> PrettyPrinterBuilder {
+        PrettyPrinterBuilder::default()
+    }
+
+    fn render(&self) -> String {
+        let mut parts: Vec<String> = self
+            .workspaces
+            .iter()
+            .enumerate()
+            .map(|(i, ws)| {
+                let focused = self.focused_ws.contains(&i);
+                (self.workspace_format)(&ws.name, focused, ws.urgent)
+            })
+            .collect();
+
+        parts.push((self.layout_format)(&self.layout_symbol));
+        parts.push((self.title_format)(&self.title));
+
+        parts.join(&self.separator)
+    }
+
+    // Open the pipe sink for writing if we don't already hold a handle to it (a no-op for
+    // Sink::Stdout). A pipe sink is almost always a FIFO (e.g. for xmobar): opening it write-only
+    // blocks until a reader attaches, so we open it O_NONBLOCK instead and simply skip this flush
+    // (retrying on the next one) if there is no reader there yet, rather than freezing the entire
+    // WindowManager event loop.
+    fn ensure_sink(&mut self) -> bool {
+        let path = match &self.sink {
+            Sink::Stdout => return true,
+            Sink::Pipe(path) => path,
+        };
+
+        if self.file.is_some() {
+            return true;
+        }
+
+        match open(path.as_str(), OFlag::O_WRONLY | OFlag::O_NONBLOCK, Mode::empty()) {
+            Ok(fd) => {
+                self.file = Some(unsafe { File::from_raw_fd(fd) });
+                true
+            }
+            Err(NixError::Sys(Errno::ENXIO)) => false, // no reader attached to the FIFO yet
+            Err(e) => {
+                warn!("unable to open PrettyPrinter sink '{}': {}", path, e);
+                false
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.ensure_sink() {
+            return;
+        }
+
+        let line = self.render();
+        match &self.sink {
+            Sink::Stdout => println!("{}", line),
+            Sink::Pipe(path) => {
+                let file = self.file.as_mut().unwrap();
+                if let Err(e) = writeln!(file, "{}", line) {
+                    warn!("unable to write to PrettyPrinter sink '{}': {}", path, e);
+                    self.file = None; // reader likely went away: re-open on the next flush
+                }
+            }
+        }
+    }
+}
+
+impl Hook for PrettyPrinter {
+    fn startup(&mut self, wm: &mut WindowManager) {
+        self.focused_ws = (0..wm.n_screens()).collect();
+        self.layout_symbol = wm.current_layout_symbol().to_string();
+        self.flush();
+    }
+
+    fn client_name_updated(&mut self, wm: &mut WindowManager, id: WinId, name: &str, is_root: bool) {
+        if !is_root && Some(id) == wm.client(&Selector::Focused).map(|c| c.id()) {
+            self.title = name.to_string();
+            self.flush();
+        }
+    }
+
+    fn layout_change(&mut self, wm: &mut WindowManager, _: usize, _: usize) {
+        self.layout_symbol = wm.current_layout_symbol().to_string();
+        self.flush();
+    }
+
+    fn workspace_change(&mut self, wm: &mut WindowManager, _: usize, new: usize) {
+        let screen = wm.active_screen_index();
+        self.focused_ws[screen] = new;
+        self.layout_symbol = wm.current_layout_symbol().to_string();
+        self.flush();
+    }
+
+    fn workspaces_updated(&mut self, wm: &mut WindowManager, names: &[&str], _: usize) {
+        let known: Vec<&str> = self.workspaces.iter().map(|w| w.name.as_ref()).collect();
+        if names != known.as_slice() {
+            self.focused_ws = wm.focused_workspaces();
+            self.workspaces = names
+                .iter()
+                .map(|&name| WsState {
+                    name: name.to_string(),
+                    urgent: false,
+                })
+                .collect();
+            self.flush();
+        }
+    }
+
+    fn screen_change(&mut self, wm: &mut WindowManager, _: usize) {
+        self.layout_symbol = wm.current_layout_symbol().to_string();
+        self.flush();
+    }
+
+    fn screens_updated(&mut self, wm: &mut WindowManager, _: &[crate::data_types::Region]) {
+        self.focused_ws = (0..wm.n_screens()).collect();
+        self.flush();
+    }
+
+    fn focus_change(&mut self, wm: &mut WindowManager, id: WinId) {
+        if let Some(client) = wm.client(&Selector::WinId(id)) {
+            self.title = client.wm_name().to_string();
+            // Mirrors Client urgency being cleared on focus: the workspace is no longer
+            // demanding attention once the user is looking at a client on it.
+            if let Some(ws) = self.workspaces.get_mut(client.workspace()) {
+                ws.urgent = false;
+            }
+            self.flush();
+        }
+    }
+
+    fn remove_client(&mut self, wm: &mut WindowManager, _: WinId) {
+        if wm.client(&Selector::Focused).is_none() {
+            self.title.clear();
+            self.flush();
+        }
+    }
+
+    fn urgent_hook(&mut self, _wm: &mut WindowManager, c: &mut Client) {
+        if let Some(ws) = self.workspaces.get_mut(c.workspace()) {
+            ws.urgent = true;
+        }
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn printer() -> PrettyPrinter {
+        let mut p = PrettyPrinter::build().sink_to_stdout().build().unwrap();
+        p.workspaces = vec![
+            WsState { name: "1".to_string(), urgent: false },
+            WsState { name: "2".to_string(), urgent: true },
+        ];
+        p.focused_ws = vec![0];
+        p.layout_symbol = "[]=".to_string();
+        p.title = "a terminal".to_string();
+        p
+    }
+
+    #[test]
+    fn build_without_a_sink_is_an_error() {
+        assert!(PrettyPrinter::build().build().is_err());
+    }
+
+    #[test]
+    fn render_joins_workspaces_layout_and_title_with_the_separator() {
+        assert_eq!(printer().render(), "1 2 []= a terminal");
+    }
+
+    #[test]
+    fn render_uses_custom_formatting_and_separator() {
+        let mut p = PrettyPrinter::build()
+            .workspace_format(|name, focused, urgent| {
+                format!("{}{}{}", if focused { ">" } else { "" }, name, if urgent { "!" } else { "" })
+            })
+            .separator(" | ")
+            .sink_to_stdout()
+            .build()
+            .unwrap();
+        p.workspaces = vec![
+            WsState { name: "1".to_string(), urgent: false },
+            WsState { name: "2".to_string(), urgent: true },
+        ];
+        p.focused_ws = vec![0];
+        p.layout_symbol = "[]=".to_string();
+        p.title = "a terminal".to_string();
+
+        assert_eq!(p.render(), ">1 | 2! | []= | a terminal");
+    }
+}
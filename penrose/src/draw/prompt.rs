@@ -0,0 +1,241 @@
+//! A minimal, blocking single line prompt for interactively reading a short piece of text from
+//! the user (e.g. a new workspace name, or a layout symbol to switch to) instead of requiring a
+//! dedicated key binding per possible value.
+use crate::{
+    core::helpers::keysyms_from_xmodmap,
+    data_types::WinId,
+    draw::{Color, Draw, DrawContext, TextStyle, WindowType},
+    xconnection::{XConn, XEvent},
+    Result,
+};
+
+use anyhow::anyhow;
+
+/// Object safe entry point for running a `Prompt`, so that it can be stored in `Config` (and
+/// from there in `WindowManager`) without making either of them generic over the `DrawContext`
+/// the prompt renders with.
+pub trait PromptRunner {
+    /// Show the prompt and block until the user submits a line of text (Return) or cancels
+    /// (Escape). `candidates` is offered for Tab-completion: each Tab press filters it down to
+    /// the entries that start with the text typed so far (case-insensitively) and extends the
+    /// input to their longest common prefix. Returns `None` if the prompt was cancelled.
+    fn get_line(
+        &mut self,
+        conn: &dyn XConn,
+        prompt: &str,
+        candidates: &[String],
+    ) -> Result<Option<String>>;
+}
+
+// What a single KeyCode should do to the in-progress input line.
+enum Key {
+    Char(char),
+    Backspace,
+    Complete,
+    Submit,
+    Cancel,
+    Ignored,
+}
+
+fn resolve_key(keysyms: &std::collections::HashMap<u8, Vec<String>>, mask: u16, code: u8) -> Key {
+    let names = match keysyms.get(&code) {
+        Some(names) => names,
+        None => return Key::Ignored,
+    };
+
+    // xmodmap lists the unshifted keysym first and the shifted one second.
+    let shifted = mask & (xcb::MOD_MASK_SHIFT as u16) != 0;
+    let name = match (shifted, names.len()) {
+        (true, n) if n > 1 => &names[1],
+        _ => match names.first() {
+            Some(name) => name,
+            None => return Key::Ignored,
+        },
+    };
+
+    match name.as_str() {
+        "Return" | "KP_Enter" => Key::Submit,
+        "Escape" => Key::Cancel,
+        "BackSpace" => Key::Backspace,
+        "space" => Key::Char(' '),
+        "Tab" | "ISO_Left_Tab" => Key::Complete,
+        s if s.chars().count() == 1 => Key::Char(s.chars().next().unwrap()),
+        _ => Key::Ignored,
+    }
+}
+
+/**
+ * Extend `input` out to the longest common prefix of the candidates that start with it
+ * (case-insensitively). Returns `None` if no candidate matches or `input` is already as long as
+ * that common prefix, so a Tab press with nothing new to offer leaves the line untouched.
+ */
+fn complete(input: &str, candidates: &[String]) -> Option<String> {
+    let needle = input.to_lowercase();
+    let matches: Vec<&String> = candidates
+        .iter()
+        .filter(|c| c.to_lowercase().starts_with(&needle))
+        .collect();
+    let first = matches.first()?;
+
+    let common: String = first
+        .chars()
+        .enumerate()
+        .take_while(|(i, ch)| matches.iter().all(|m| m.chars().nth(*i) == Some(*ch)))
+        .map(|(_, ch)| ch)
+        .collect();
+
+    if common.chars().count() > input.chars().count() {
+        Some(common)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::complete;
+
+    #[test]
+    fn complete_extends_to_common_prefix() {
+        let candidates = vec!["work".to_string(), "workshop".to_string(), "home".to_string()];
+        assert_eq!(complete("wo", &candidates), Some("work".to_string()));
+    }
+
+    #[test]
+    fn complete_is_case_insensitive() {
+        let candidates = vec!["Main".to_string()];
+        assert_eq!(complete("ma", &candidates), Some("Main".to_string()));
+    }
+
+    #[test]
+    fn complete_returns_none_with_no_new_characters_to_add() {
+        let candidates = vec!["work".to_string()];
+        assert_eq!(complete("work", &candidates), None);
+    }
+
+    #[test]
+    fn complete_returns_none_with_no_matching_candidates() {
+        let candidates = vec!["work".to_string(), "home".to_string()];
+        assert_eq!(complete("xyz", &candidates), None);
+    }
+}
+
+/**
+ * A simple single line prompt that takes exclusive control of the keyboard while reading user
+ * input, rendering the text typed so far using the supplied [Draw] implementation.
+ *
+ * Unlike [StatusBar][crate::draw::StatusBar], a `Prompt` is not a `Hook`: it is driven directly
+ * by `WindowManager` methods (`rename_workspace`, `select_workspace`, `layout_prompt`) that need
+ * to block and read a short piece of text from the user before continuing.
+ */
+pub struct Prompt<Ctx> {
+    drw: Box<dyn Draw<Ctx = Ctx>>,
+    style: TextStyle,
+    bg: Color,
+    h: usize,
+}
+
+impl<Ctx: DrawContext> Prompt<Ctx> {
+    /// Construct a new Prompt that will render using 'drw', with the given text style, background
+    /// color and height in pixels.
+    pub fn new(
+        drw: Box<dyn Draw<Ctx = Ctx>>,
+        style: TextStyle,
+        bg: impl Into<Color>,
+        h: usize,
+    ) -> Self {
+        Self {
+            drw,
+            style,
+            bg: bg.into(),
+            h,
+        }
+    }
+
+    fn render(&mut self, id: WinId, w: f64, prompt: &str, input: &str) -> Result<()> {
+        let mut ctx = self.drw.context_for(id)?;
+
+        ctx.clear();
+        ctx.color(&self.bg);
+        ctx.rectangle(0.0, 0.0, w, self.h as f64);
+
+        ctx.font(&self.style.font, self.style.point_size)?;
+        ctx.color(&self.style.fg);
+        ctx.text(&format!("{}{}", prompt, input), 0.0, self.style.padding)?;
+        ctx.flush();
+        self.drw.flush(id);
+
+        Ok(())
+    }
+}
+
+impl<Ctx: DrawContext> PromptRunner for Prompt<Ctx> {
+    fn get_line(
+        &mut self,
+        conn: &dyn XConn,
+        prompt: &str,
+        candidates: &[String],
+    ) -> Result<Option<String>> {
+        let (sx, sy, sw, _) = self
+            .drw
+            .screen_sizes()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no screens to show prompt on"))?
+            .values();
+
+        let id = self.drw.new_window(
+            &WindowType::Dock,
+            sx as usize,
+            sy as usize,
+            sw as usize,
+            self.h,
+        )?;
+        self.drw.register_font(&self.style.font);
+        self.drw.map_window(id);
+
+        if let Err(e) = conn.grab_keyboard() {
+            // The window is already created and mapped at this point: tear it back down rather
+            // than leaving a dead, unfocusable prompt box on screen.
+            self.drw.unmap_window(id);
+            self.drw.destroy_window(id);
+            return Err(e);
+        }
+
+        let keysyms = keysyms_from_xmodmap();
+        let mut input = String::new();
+        let result = loop {
+            if let Err(e) = self.render(id, sw as f64, prompt, &input) {
+                warn!("error rendering prompt: {}", e);
+            }
+
+            match conn.wait_for_event() {
+                Some(XEvent::KeyPress(key_code)) => {
+                    match resolve_key(&keysyms, key_code.mask, key_code.code) {
+                        Key::Char(c) => input.push(c),
+                        Key::Backspace => {
+                            input.pop();
+                        }
+                        Key::Complete => {
+                            if let Some(completed) = complete(&input, candidates) {
+                                input = completed;
+                            }
+                        }
+                        Key::Submit => break Some(input),
+                        Key::Cancel => break None,
+                        Key::Ignored => (),
+                    }
+                }
+                Some(_) => (),
+                None => break None,
+            }
+        };
+
+        conn.ungrab_keyboard()?;
+        self.drw.unmap_window(id);
+        self.drw.destroy_window(id);
+        conn.flush();
+
+        Ok(result)
+    }
+}
@@ -11,16 +11,16 @@ extern crate penrose;
 
 use penrose::{
     client::Client,
-    draw::{dwm_bar, TextStyle, XCBDraw},
+    draw::{dwm_bar, PrettyPrinter, Prompt, TextStyle, XCBDraw},
     contrib::{
         extensions::Scratchpad,
-        hooks::{DefaultWorkspace, LayoutSymbolAsRootName},
+        hooks::{DefaultWorkspace, EwmhHook, LayoutSymbolAsRootName},
         layouts::paper,
     },
     helpers::{index_selectors, spawn},
     hooks::Hook,
     layout::{bottom_stack, side_stack, Layout, LayoutConf},
-    Backward, Config, Forward, Less, More, Result, Selector, WindowManager, XcbConnection,
+    Backward, Config, Forward, Less, More, Result, Selector, Snap, WindowManager, XcbConnection,
 };
 
 use simplelog::{LevelFilter, SimpleLogger};
@@ -35,6 +35,15 @@ impl Hook for MyClientHook {
     }
 }
 
+// Fired whenever a client sets the ICCCM urgency hint or _NET_WM_STATE_DEMANDS_ATTENTION. Here we
+// just log it, but you could equally well spawn a notification or jump straight to the client.
+struct MyUrgencyHook {}
+impl Hook for MyUrgencyHook {
+    fn urgent_hook(&mut self, wm: &mut WindowManager, c: &mut Client) {
+        wm.log(&format!("client '{}' is demanding attention", c.wm_class()));
+    }
+}
+
 const HEIGHT: usize = 18;
 const PROFONT: &str = "JetBrainsMono Nerd Font";
 
@@ -57,11 +66,40 @@ fn main() -> Result<()> {
     config.workspaces = vec!["1", "2", "3", "4", "5", "6", "7", "8", "9"];
 
     // Windows with a matching WM_CLASS will always float
-    config.floating_classes = &["dmenu", "dunst", "polybar", "rofi"];
+    config.floating_classes = &["dmenu", "dunst", "rofi"];
 
     // Client border colors are set based on X focus
     config.focused_border = 0xf59342; // #cc241d
     config.unfocused_border = 0x3c3836; // #3c3836
+    config.urgent_border = 0xcc241d; // repainted on clients that raise WM_HINTS urgency
+
+    // picom is already spawned below to composite the opacity penrose sets via
+    // _NET_WM_WINDOW_OPACITY, so unfocused windows get dimmed on every focus change.
+    config.active_opacity = 1.0;
+    config.inactive_opacity = 0.85;
+    // A couple of WM_CLASSes that should stay fully opaque even when unfocused.
+    config.opacity_overrides.insert("rofi".to_string(), 1.0);
+    config.opacity_overrides.insert("dunst".to_string(), 1.0);
+
+    // Multi-monitor setup: keep the pointer glued to whichever client has keyboard focus so that
+    // focus-follows-mouse window managers running alongside don't steal focus back when we jump
+    // screens on a keybinding.
+    config.pointer_warp = Some(Snap::Center);
+
+    // Backs "M-r" / "M-w" / "M-S-space" below: a small bar-like window that takes over the
+    // keyboard to read a single line of text from the user.
+    config.prompt = Some(Box::new(Prompt::new(
+        Box::new(XCBDraw::new()?),
+        TextStyle {
+            font: PROFONT.to_string(),
+            point_size: 11,
+            fg: WHITE.into(),
+            bg: Some(BLACK.into()),
+            padding: (2.0, 2.0),
+        },
+        BLACK,
+        HEIGHT,
+    )));
 
     config.hooks.push(Box::new(dwm_bar(
                 Box::new(XCBDraw::new()?),
@@ -78,6 +116,27 @@ fn main() -> Result<()> {
                 &config.workspaces,
             )?));
 
+    // dwm_bar renders its own bar with XCBDraw, but if you would rather have an external bar such
+    // as xmobar render your status line, a PrettyPrinter can feed it the same workspace / title /
+    // layout state as a single templated line written to a named pipe instead.
+    config.hooks.push(Box::new(
+        PrettyPrinter::build()
+            .workspace_format(|ws, focused, urgent| {
+                if urgent {
+                    format!("<fc=#cc241d>{}</fc>", ws)
+                } else if focused {
+                    format!("<fc=#f59342>{}</fc>", ws)
+                } else {
+                    ws.to_string()
+                }
+            })
+            .title_format(|title| format!("<fc=#ebdbb2>{}</fc>", title))
+            .layout_format(|symbol| format!("<fc=#7e7e7e>{}</fc>", symbol))
+            .separator(" } ")
+            .sink_to_pipe("/tmp/penrose.fifo")
+            .build()?,
+    ));
+
     // When specifying a layout, most of the time you will want LayoutConf::default() as shown
     // below, which will honour gap settings and will not be run on focus changes (only when
     // clients are added/removed). To customise when/how each layout is applied you can create a
@@ -95,8 +154,7 @@ fn main() -> Result<()> {
     // Default percentage of the screen to fill with the main area of the layout
     let ratio = 0.55;
 
-    // Layouts to be used on each workspace. Currently all workspaces have the same set of Layouts
-    // available to them, though they track modifications to n_main and ratio independently.
+    // Layouts to be used on each workspace by default.
     config.layouts = vec![
         Layout::new("[side]", LayoutConf::default(), side_stack, n_main, ratio),
         Layout::new("[botm]", LayoutConf::default(), bottom_stack, n_main, ratio),
@@ -104,6 +162,17 @@ fn main() -> Result<()> {
         Layout::floating("[----]"),
     ];
 
+    // Workspace "9" is our IM/chat workspace (see the DefaultWorkspace hook below), so give it its
+    // own narrower roster layout instead of the coding-focused set above: the chat windows stay
+    // pinned in a slim side column with everything else maximised next to them.
+    config.per_workspace_layouts.insert(
+        "9".to_string(),
+        vec![
+            Layout::new("[ros]", LayoutConf::default(), side_stack, n_main, 0.25),
+            Layout::floating("[----]"),
+        ],
+    );
+
     // NOTE: change these to programs that you have installed!
     let my_program_launcher = "rofi -combi-modi run,drun,window -show combi";
     let my_file_manager = "alacritty -e ranger";
@@ -120,6 +189,13 @@ fn main() -> Result<()> {
      * modify their behaviour if desired.
      */
     config.hooks.push(Box::new(MyClientHook {}));
+    config.hooks.push(Box::new(MyUrgencyHook {}));
+
+    // Publishes _NET_SUPPORTED, _NET_CLIENT_LIST, _NET_CURRENT_DESKTOP and friends, and keeps them
+    // up to date as clients and workspaces change, so that EWMH pagers/taskbars (and wmctrl) can
+    // see and drive penrose. Push this before other hooks that also care about client/workspace
+    // bookkeeping so the exposed state is already current when they run.
+    config.hooks.push(EwmhHook::new(&config.workspaces));
 
     // Using a simple contrib hook that takes no config. By convention, contrib hooks have a 'new'
     // method that returns a boxed instance of the hook with any configuration performed so that it
@@ -147,7 +223,7 @@ fn main() -> Result<()> {
     /* The gen_keybindings macro parses user friendly key binding definitions into X keycodes and
      * modifier masks. It uses the 'xmodmap' program to determine your current keymap and create
      * the bindings dynamically on startup. If this feels a little too magical then you can
-     * alternatively construct a  HashMap<KeyCode, FireAndForget> manually with your chosen
+     * alternatively construct a KeyBindings collection manually with your chosen
      * keybindings (see helpers.rs and data_types.rs for details).
      * FireAndForget functions do not need to make use of the mutable WindowManager reference they
      * are passed if it is not required: the run_external macro ignores the WindowManager itself
@@ -171,6 +247,7 @@ fn main() -> Result<()> {
         "M-S-j" => run_internal!(drag_client, Forward);
         "M-S-k" => run_internal!(drag_client, Backward);
         "M-S-c" => run_internal!(kill_client);
+        "M-u" => run_internal!(focus_urgent);
         "M-S-f" => run_internal!(toggle_client_fullscreen, &Selector::Focused);
         "M-slash" => sp.toggle();
         "M-comma" => fr.toggle();
@@ -182,6 +259,14 @@ fn main() -> Result<()> {
         "M-S-bracketright" => run_internal!(drag_workspace, Forward);
         "M-S-bracketleft" => run_internal!(drag_workspace, Backward);
 
+        // Native Prompt widget (see draw::Prompt) instead of shelling out to rofi/dmenu for
+        // workspace/layout selection: it grabs the keyboard itself and reads a single line of
+        // text, submitted with Return or cancelled with Escape. Tab completes the line typed so
+        // far against the existing workspace names / layout symbols, where applicable.
+        "M-r" => run_internal!(rename_workspace);
+        "M-w" => run_internal!(select_workspace);
+        "M-S-space" => run_internal!(layout_prompt);
+
         // Layout management
         "M-period" => run_internal!(cycle_layout, Forward);
         "M-S-period" => run_internal!(cycle_layout, Backward);
@@ -193,6 +278,16 @@ fn main() -> Result<()> {
         "M-A-s" => run_internal!(detect_screens);
         "M-S-Escape" => run_internal!(exit);
 
+        // Re-resolves the configured KeyCodes from the current keymap and re-grabs them, so that
+        // running xmodmap/setxkbmap to swap Super and Alt (or any other remap) takes effect
+        // without having to restart penrose.
+        "M-C-r" => run_internal!(refresh_keymap);
+
+        // polybar reserves screen space itself via _NET_WM_STRUT_PARTIAL, so it no longer needs to
+        // be listed in config.floating_classes above. Bind a key to ignore struts temporarily (e.g.
+        // while a panel is being repositioned) rather than restarting penrose.
+        "M-C-s" => run_internal!(toggle_struts);
+
         // Each keybinding here will be templated in with the workspace index of each workspace,
         // allowing for common workspace actions to be bound at once.
         refmap [ config.ws_range() ] in {